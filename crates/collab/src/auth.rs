@@ -1,7 +1,7 @@
 use crate::{
-    AppState, Error, Result,
     db::{Database, NewUserParams, User, UserId},
     rpc::Principal,
+    AppState, Error, Result,
 };
 use anyhow::Context as _;
 use axum::{
@@ -9,16 +9,381 @@ use axum::{
     middleware::Next,
     response::IntoResponse,
 };
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
 use cloud_api_types::GetAuthenticatedUserResponse;
+use ed25519_dalek::{Signature, Verifier as _, VerifyingKey};
+use lru::LruCache;
 pub use rpc::auth::random_token;
+use serde::Deserialize;
 use sha2::Digest;
-use std::sync::Arc;
+use std::{
+    collections::HashMap,
+    num::NonZeroUsize,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+mod github;
+use github::GitHubUser;
+
+/// Builds the `http_client` used for `zed_cloud` token validation; consumed by `AppState`
+/// construction so deployments behind an internal PKI can pin a custom root CA.
+pub mod http_client;
+
+/// How long a challenge nonce handed out for `zed-key` auth remains valid.
+const CHALLENGE_NONCE_TTL: Duration = Duration::from_secs(30);
+
+/// Tracks outstanding `zed-key` challenge nonces, keyed by the user they were issued to.
+///
+/// Entries are single-use: a successful verification (or expiry) removes them, so a captured
+/// signature cannot be replayed against a later request.
+#[derive(Default)]
+pub struct NonceStore {
+    nonces: Mutex<HashMap<UserId, ([u8; 32], Instant)>>,
+}
+
+impl NonceStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Issues a nonce for `user_id`. If one is already outstanding and hasn't expired, returns
+    /// that same nonce instead of minting a new one: otherwise an unauthenticated caller who
+    /// knows a victim's user id could repeatedly hit the challenge endpoint to stomp a nonce the
+    /// legitimate client is mid-flight on signing, turning issuance into a denial-of-service
+    /// against that user's zed-key logins.
+    pub fn issue(&self, user_id: UserId) -> [u8; 32] {
+        let mut nonces = self.nonces.lock().unwrap();
+        if let Some((nonce, issued_at)) = nonces.get(&user_id) {
+            if issued_at.elapsed() < CHALLENGE_NONCE_TTL {
+                return *nonce;
+            }
+        }
+
+        let nonce: [u8; 32] = rand::random();
+        nonces.insert(user_id, (nonce, Instant::now()));
+        nonce
+    }
+
+    /// Consumes and returns the outstanding nonce for `user_id`, if one exists and has not
+    /// expired. Single-use: a second call for the same user returns `None` until a new nonce
+    /// is issued.
+    fn take(&self, user_id: UserId) -> Option<[u8; 32]> {
+        let (nonce, issued_at) = self.nonces.lock().unwrap().remove(&user_id)?;
+        (issued_at.elapsed() < CHALLENGE_NONCE_TTL).then_some(nonce)
+    }
+}
+
+/// How close to its upstream-reported expiry a cached access token must be before
+/// `validate_header` proactively refreshes it instead of waiting for a 401.
+const PROACTIVE_REFRESH_WINDOW: Duration = Duration::from_secs(30);
+
+/// A resolved `users/me` validation, cached so repeated requests with the same token skip the
+/// round-trip to `zed_cloud`.
+struct ValidationCacheEntry {
+    user: User,
+    /// The refresh token to use if this entry's access token needs proactive refresh, carried
+    /// over from whichever request (initial login or a prior refresh) last supplied one.
+    refresh_token: Option<String>,
+    /// When the upstream access token itself expires, per the token endpoint's `expires_in`.
+    /// `None` when the access token came from a direct login rather than a refresh exchange,
+    /// since `users/me` validation doesn't report an expiry.
+    access_token_expires_at: Option<Instant>,
+    /// When this cache entry stops being trusted and `validate_header` re-validates against
+    /// `zed_cloud`, independent of the access token's own expiry.
+    expires_at: Instant,
+}
+
+/// The result of a validation-cache hit: the resolved user, plus enough information for
+/// `validate_header` to proactively refresh the access token before it actually expires.
+struct CachedValidation {
+    user: User,
+    refresh_token: Option<String>,
+    needs_refresh: bool,
+}
+
+/// Caches successful upstream token validations, keyed by a salted hash of
+/// `(user_id, access_token)` so the raw access token is never retained in memory. Bounded by
+/// `capacity` with LRU eviction so a flood of distinct tokens cannot exhaust memory.
+pub struct ValidationCache {
+    entries: Mutex<LruCache<[u8; 32], ValidationCacheEntry>>,
+    ttl: Duration,
+}
+
+impl ValidationCache {
+    pub fn new(capacity: usize, ttl: Duration) -> Self {
+        Self {
+            entries: Mutex::new(LruCache::new(
+                NonZeroUsize::new(capacity).unwrap_or(NonZeroUsize::new(1).unwrap()),
+            )),
+            ttl,
+        }
+    }
+
+    fn key(salt: &str, user_id: UserId, access_token: &str) -> [u8; 32] {
+        let mut hasher = sha2::Sha256::new();
+        hasher.update(salt.as_bytes());
+        hasher.update(user_id.0.to_be_bytes());
+        hasher.update(access_token.as_bytes());
+        hasher.finalize().into()
+    }
+
+    /// Returns the cached validation for `(user_id, access_token)`, if present and not expired.
+    fn get(&self, salt: &str, user_id: UserId, access_token: &str) -> Option<CachedValidation> {
+        let key = Self::key(salt, user_id, access_token);
+        let mut entries = self.entries.lock().unwrap();
+        let entry = entries.get(&key)?;
+        if entry.expires_at < Instant::now() {
+            entries.pop(&key);
+            return None;
+        }
+
+        let needs_refresh = entry
+            .access_token_expires_at
+            .is_some_and(|expires_at| expires_at <= Instant::now() + PROACTIVE_REFRESH_WINDOW);
+
+        Some(CachedValidation {
+            user: entry.user.clone(),
+            refresh_token: entry.refresh_token.clone(),
+            needs_refresh,
+        })
+    }
+
+    /// Records a successful validation for `(user_id, access_token)`. `refresh_token` and
+    /// `expires_in` (from the token endpoint, when this validation followed a refresh) let a
+    /// later cache hit proactively refresh before the access token actually expires.
+    fn insert(
+        &self,
+        salt: &str,
+        user_id: UserId,
+        access_token: &str,
+        user: User,
+        refresh_token: Option<String>,
+        expires_in: Option<u64>,
+    ) {
+        let key = Self::key(salt, user_id, access_token);
+        self.entries.lock().unwrap().put(
+            key,
+            ValidationCacheEntry {
+                user,
+                refresh_token,
+                access_token_expires_at: expires_in
+                    .map(|secs| Instant::now() + Duration::from_secs(secs)),
+                expires_at: Instant::now() + self.ttl,
+            },
+        );
+    }
+
+    /// Evicts any cached entry for `(user_id, access_token)`, e.g. after an upstream 401.
+    fn invalidate(&self, salt: &str, user_id: UserId, access_token: &str) {
+        let key = Self::key(salt, user_id, access_token);
+        self.entries.lock().unwrap().pop(&key);
+    }
+}
+
+/// Issues a `zed-key` challenge nonce for `user_id`, or returns the one still outstanding if
+/// it has not expired. Called by the
+/// `/authenticate/challenge` route before the client signs `nonce || method || path` and
+/// retries the request with `Authorization: zed-key`.
+pub fn issue_challenge_nonce(state: &Arc<AppState>, user_id: UserId) -> [u8; 32] {
+    state.nonce_store.issue(user_id)
+}
+
+/// Response from the `zed_cloud` OAuth2 token endpoint when exchanging a refresh token for a
+/// new access token.
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    /// Some token endpoints rotate the refresh token on every exchange; when present, this
+    /// replaces the refresh token cached alongside the access token so the next proactive
+    /// refresh uses it instead of the one that was just redeemed.
+    refresh_token: Option<String>,
+    /// How long the access token is valid for, cached alongside it so `validate_header` can
+    /// proactively refresh before it expires rather than waiting for a 401.
+    expires_in: Option<u64>,
+}
+
+/// Calls `{zed_cloud_url}/client/users/me` with `access_token` and returns the raw response,
+/// leaving status handling to the caller so a 401 can trigger a refresh-and-retry.
+async fn validate_access_token(
+    http_client: &reqwest::Client,
+    state: &Arc<AppState>,
+    user_id: UserId,
+    access_token: &str,
+) -> Result<reqwest::Response> {
+    http_client
+        .get(format!("{}/client/users/me", state.config.zed_cloud_url()))
+        .header("Content-Type", "application/json")
+        .header("Authorization", format!("{user_id} {access_token}"))
+        .send()
+        .await
+        .context("failed to validate access token")
+        .map_err(Into::into)
+}
+
+/// Calls the configured token endpoint with `refresh_token` and returns the raw response,
+/// leaving status handling to the caller so a rejected refresh can be shaped by
+/// `upstream_validation_error` just like a rejected access token.
+async fn refresh_access_token(
+    http_client: &reqwest::Client,
+    state: &Arc<AppState>,
+    refresh_token: &str,
+) -> Result<reqwest::Response> {
+    http_client
+        .post(state.config.zed_cloud_token_url())
+        .form(&[
+            ("grant_type", "refresh_token"),
+            ("refresh_token", refresh_token),
+        ])
+        .send()
+        .await
+        .context("failed to reach token endpoint")
+        .map_err(Into::into)
+}
+
+/// Exchanges `refresh_token` for a new access token and validates it against `zed_cloud`,
+/// returning both the token response (so the caller can cache it for a future proactive
+/// refresh) and the resolved user. Shared by the reactive (post-401) and proactive
+/// (near-expiry) refresh paths in `validate_header`.
+async fn refresh_and_validate(
+    http_client: &reqwest::Client,
+    state: &Arc<AppState>,
+    user_id: UserId,
+    refresh_token: &str,
+) -> Result<(TokenResponse, User)> {
+    let refresh_response = refresh_access_token(http_client, state, refresh_token).await?;
+    if !refresh_response.status().is_success() {
+        return Err(upstream_validation_error(user_id, refresh_response).await);
+    }
+    let token_response: TokenResponse = refresh_response
+        .json()
+        .await
+        .context("failed to parse token endpoint response")?;
+
+    let retried =
+        validate_access_token(http_client, state, user_id, &token_response.access_token).await?;
+    let user = user_from_validation_response(state, user_id, retried).await?;
+
+    Ok((token_response, user))
+}
+
+/// Turns a successful `users/me` response into the corresponding `User`. On an unsuccessful
+/// status, shapes the error via `upstream_validation_error` rather than collapsing everything
+/// into a generic "invalid credentials" and discarding the upstream body.
+async fn user_from_validation_response(
+    state: &Arc<AppState>,
+    requesting_user_id: UserId,
+    response: reqwest::Response,
+) -> Result<User> {
+    if !response.status().is_success() {
+        return Err(upstream_validation_error(requesting_user_id, response).await);
+    }
+
+    let response_body: GetAuthenticatedUserResponse = response
+        .json()
+        .await
+        .context("failed to parse response body")?;
+
+    let user_id = UserId(response_body.user.id);
+
+    state
+        .db
+        .get_user_by_id(user_id)
+        .await?
+        .with_context(|| format!("user {user_id} not found"))
+        .map_err(Into::into)
+}
+
+/// Classifies an upstream authentication failure into a reason code the client can act on
+/// (e.g. retry later vs. re-authenticate), without leaking the raw, possibly secret-bearing
+/// upstream body to the client.
+#[derive(Debug, Clone, Copy)]
+enum UpstreamAuthReason {
+    RateLimited,
+    TokenRevoked,
+    AccountSuspended,
+    Unavailable,
+    InvalidCredentials,
+}
+
+impl UpstreamAuthReason {
+    fn from_status(status: StatusCode) -> Self {
+        match status {
+            StatusCode::TOO_MANY_REQUESTS => Self::RateLimited,
+            StatusCode::FORBIDDEN => Self::AccountSuspended,
+            StatusCode::UNAUTHORIZED => Self::TokenRevoked,
+            status if status.is_server_error() => Self::Unavailable,
+            _ => Self::InvalidCredentials,
+        }
+    }
+
+    fn code(self) -> &'static str {
+        match self {
+            Self::RateLimited => "rate_limited",
+            Self::TokenRevoked => "token_revoked",
+            Self::AccountSuspended => "account_suspended",
+            Self::Unavailable => "upstream_unavailable",
+            Self::InvalidCredentials => "invalid_credentials",
+        }
+    }
+
+    /// The status returned to the client for this reason. `RateLimited`/`AccountSuspended`/
+    /// `Unavailable` get a distinct, non-401 status so Zed can tell "retry later" apart from
+    /// "re-authenticate" without having to parse the body; only `TokenRevoked` and
+    /// `InvalidCredentials` actually mean the credentials themselves are no good.
+    fn status(self) -> StatusCode {
+        match self {
+            Self::RateLimited => StatusCode::TOO_MANY_REQUESTS,
+            Self::AccountSuspended => StatusCode::FORBIDDEN,
+            Self::Unavailable => StatusCode::SERVICE_UNAVAILABLE,
+            Self::TokenRevoked | Self::InvalidCredentials => StatusCode::UNAUTHORIZED,
+        }
+    }
+}
+
+/// Builds the `Error` returned to the client for a failed upstream validation response,
+/// first logging the upstream status and body at warn level for operational debugging. This
+/// is shared by every path that validates against `zed_cloud` (the numeric user-id path and
+/// its refresh exchange). The github-login branch does not call `zed_cloud` and does not use
+/// it: its failures are returned as a plain "invalid credentials" 401.
+async fn upstream_validation_error(user_id: UserId, response: reqwest::Response) -> Error {
+    let status = response.status();
+    let body = response
+        .text()
+        .await
+        .unwrap_or_else(|_| "<failed to read body>".to_string());
+
+    tracing::warn!(
+        "upstream token validation failed for user {user_id}: status={status} body={body}"
+    );
+
+    let reason = UpstreamAuthReason::from_status(status);
+    Error::http(reason.status(), reason.code().to_string())
+}
+
+/// Stamps a rotated access token onto the response so the caller can pick it up without being
+/// asked to re-login; silently dropped if it somehow isn't a valid header value.
+fn attach_refreshed_token_header(response: &mut axum::response::Response, access_token: &str) {
+    if let Ok(header_value) = http::HeaderValue::from_str(access_token) {
+        response
+            .headers_mut()
+            .insert("X-Zed-Access-Token", header_value);
+    }
+}
 
 /// Validates the authorization header and adds an Extension<Principal> to the request.
-/// Authorization: <user-id|github-login> <token>
+/// Authorization: <user-id|github-login> <token> [<refresh-token>]
 ///   <token> can be an access_token attached to that user, or an access token of an admin
-///   or the string ADMIN_TOKEN:<config.api_token>.
+///   or the string ADMIN_TOKEN:<config.api_token>. If the upstream validation of <token>
+///   returns 401, a refresh token supplied either as a third segment here or via the
+///   `X-Zed-Refresh` header is exchanged for a new access token and the validation is retried
+///   once; on success the rotated access token is returned via `X-Zed-Access-Token`. The same
+///   refresh token is also cached alongside a cached validation so it can be redeemed
+///   proactively, ahead of the access token's own expiry, rather than waiting for a 401.
 /// Authorization: "dev-server-token" <token>
+/// Authorization: "zed-key" <user-id> <base64-signature>
+///   <base64-signature> signs `nonce || method || path` with the user's Ed25519 secret key,
+///   where `nonce` is a short-lived challenge previously issued by `issue_challenge_nonce`.
 pub async fn validate_header<B>(mut req: Request<B>, next: Next<B>) -> impl IntoResponse {
     let mut auth_header = req
         .headers()
@@ -42,6 +407,33 @@ pub async fn validate_header<B>(mut req: Request<B>, next: Next<B>) -> impl Into
         ))?;
     }
 
+    if first == "zed-key" {
+        let user_id = auth_header
+            .next()
+            .and_then(|segment| segment.parse::<i32>().ok())
+            .map(UserId)
+            .ok_or_else(|| {
+                Error::http(
+                    StatusCode::BAD_REQUEST,
+                    "missing user id in zed-key authorization header".to_string(),
+                )
+            })?;
+
+        let signature = auth_header.next().ok_or_else(|| {
+            Error::http(
+                StatusCode::BAD_REQUEST,
+                "missing signature in zed-key authorization header".to_string(),
+            )
+        })?;
+
+        let method = req.method().clone();
+        let path = req.uri().path().to_string();
+
+        let user = verify_zed_key_challenge(state, user_id, signature, &method, &path).await?;
+        req.extensions_mut().insert(Principal::User(user));
+        return Ok::<_, Error>(next.run(req).await);
+    }
+
     let access_token = auth_header.next().ok_or_else(|| {
         Error::http(
             StatusCode::BAD_REQUEST,
@@ -52,37 +444,100 @@ pub async fn validate_header<B>(mut req: Request<B>, next: Next<B>) -> impl Into
     if let Ok(user_id) = first.parse::<i32>() {
         let user_id = UserId(user_id);
         let http_client = state.http_client.clone().expect("no HTTP client");
+        let refresh_token = auth_header.next().map(str::to_string).or_else(|| {
+            req.headers()
+                .get("X-Zed-Refresh")
+                .and_then(|header| header.to_str().ok())
+                .map(str::to_string)
+        });
+
+        let cache_salt = &state.config.api_token;
+        if let Some(cached) = state
+            .validation_cache
+            .get(cache_salt, user_id, access_token)
+        {
+            if !cached.needs_refresh {
+                req.extensions_mut().insert(Principal::User(cached.user));
+                return Ok::<_, Error>(next.run(req).await);
+            }
 
-        let response = http_client
-            .get(format!("{}/client/users/me", state.config.zed_cloud_url()))
-            .header("Content-Type", "application/json")
-            .header("Authorization", format!("{user_id} {access_token}"))
-            .send()
-            .await
-            .context("failed to validate access token")?;
+            if let Some(refresh_token) = cached.refresh_token.clone() {
+                match refresh_and_validate(&http_client, state, user_id, &refresh_token).await {
+                    Ok((token_response, user)) => {
+                        state.validation_cache.insert(
+                            cache_salt,
+                            user_id,
+                            &token_response.access_token,
+                            user.clone(),
+                            token_response
+                                .refresh_token
+                                .clone()
+                                .or_else(|| Some(refresh_token.clone())),
+                            token_response.expires_in,
+                        );
+                        req.extensions_mut().insert(Principal::User(user));
 
-        if let Ok(response) = response.error_for_status() {
-            let response_body: GetAuthenticatedUserResponse = response
-                .json()
-                .await
-                .context("failed to parse response body")?;
+                        let mut response = next.run(req).await;
+                        attach_refreshed_token_header(&mut response, &token_response.access_token);
+                        return Ok::<_, Error>(response);
+                    }
+                    Err(error) => {
+                        // The cached validation is still within its TTL, so the request is
+                        // served with it even if the access token is already past its upstream
+                        // expiry; just log and retry the refresh on a later request.
+                        tracing::warn!(
+                            "proactive refresh failed for user {user_id}, serving cached validation: {error:?}"
+                        );
+                    }
+                }
+            }
 
-            let user_id = UserId(response_body.user.id);
+            req.extensions_mut().insert(Principal::User(cached.user));
+            return Ok::<_, Error>(next.run(req).await);
+        }
 
-            let user = state
-                .db
-                .get_user_by_id(user_id)
-                .await?
-                .with_context(|| format!("user {user_id} not found"))?;
+        let response = validate_access_token(&http_client, state, user_id, access_token).await?;
 
+        if response.status() != StatusCode::UNAUTHORIZED {
+            let user = user_from_validation_response(state, user_id, response).await?;
+            state.validation_cache.insert(
+                cache_salt,
+                user_id,
+                access_token,
+                user.clone(),
+                refresh_token.clone(),
+                None,
+            );
             req.extensions_mut().insert(Principal::User(user));
             return Ok::<_, Error>(next.run(req).await);
         }
 
-        return Err(Error::http(
-            StatusCode::UNAUTHORIZED,
-            "invalid credentials".to_string(),
-        ));
+        state
+            .validation_cache
+            .invalidate(cache_salt, user_id, access_token);
+
+        let Some(refresh_token) = refresh_token else {
+            return Err(upstream_validation_error(user_id, response).await);
+        };
+
+        let (token_response, user) =
+            refresh_and_validate(&http_client, state, user_id, &refresh_token).await?;
+        state.validation_cache.insert(
+            cache_salt,
+            user_id,
+            &token_response.access_token,
+            user.clone(),
+            token_response
+                .refresh_token
+                .clone()
+                .or_else(|| Some(refresh_token.clone())),
+            token_response.expires_in,
+        );
+        req.extensions_mut().insert(Principal::User(user));
+
+        let mut response = next.run(req).await;
+        attach_refreshed_token_header(&mut response, &token_response.access_token);
+        return Ok::<_, Error>(response);
     }
 
     let github_login = first.trim();
@@ -101,6 +556,8 @@ pub async fn validate_header<B>(mut req: Request<B>, next: Next<B>) -> impl Into
         ));
     }
 
+    // Admin-token and github-login requests never hit `zed_cloud`, so there is no round-trip
+    // for `validation_cache` to save here; it is only consulted on the numeric user-id path.
     let Some(admin_token) = access_token.strip_prefix("ADMIN_TOKEN:") else {
         return Err(Error::http(
             StatusCode::UNAUTHORIZED,
@@ -115,11 +572,76 @@ pub async fn validate_header<B>(mut req: Request<B>, next: Next<B>) -> impl Into
         ));
     }
 
-    let user = get_or_create_user_for_trusted_login(&github_login, &state.db).await?;
+    let user = get_or_create_user_for_trusted_login(&github_login, state).await?;
     req.extensions_mut().insert(Principal::User(user));
     Ok::<_, Error>(next.run(req).await)
 }
 
+/// Verifies a `zed-key` challenge-response signature for `user_id` and returns the
+/// authenticated user. The signed message is `nonce || method || path`, where `nonce` is the
+/// single-use challenge previously issued via `issue_challenge_nonce`.
+async fn verify_zed_key_challenge(
+    state: &Arc<AppState>,
+    user_id: UserId,
+    signature_b64: &str,
+    method: &http::Method,
+    path: &str,
+) -> Result<User> {
+    let user = state
+        .db
+        .get_user_by_id(user_id)
+        .await?
+        .with_context(|| format!("user {user_id} not found"))?;
+
+    let Some(public_key_b64) = user.ed25519_public_key.as_deref() else {
+        return Err(Error::http(
+            StatusCode::UNAUTHORIZED,
+            "user has no registered key".to_string(),
+        ));
+    };
+
+    let Some(nonce) = state.nonce_store.take(user_id) else {
+        return Err(Error::http(
+            StatusCode::UNAUTHORIZED,
+            "challenge nonce is unknown or expired".to_string(),
+        ));
+    };
+
+    let public_key_bytes: [u8; 32] = BASE64
+        .decode(public_key_b64)
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| {
+            Error::http(
+                StatusCode::UNAUTHORIZED,
+                "user has no registered key".to_string(),
+            )
+        })?;
+    let verifying_key = VerifyingKey::from_bytes(&public_key_bytes).map_err(|_| {
+        Error::http(
+            StatusCode::UNAUTHORIZED,
+            "user has no registered key".to_string(),
+        )
+    })?;
+
+    let signature_bytes: [u8; 64] = BASE64
+        .decode(signature_b64)
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| Error::http(StatusCode::UNAUTHORIZED, "invalid credentials".to_string()))?;
+    let signature = Signature::from_bytes(&signature_bytes);
+
+    let mut message = nonce.to_vec();
+    message.extend_from_slice(method.as_str().as_bytes());
+    message.extend_from_slice(path.as_bytes());
+
+    verifying_key
+        .verify(&message, &signature)
+        .map_err(|_| Error::http(StatusCode::UNAUTHORIZED, "invalid credentials".to_string()))?;
+
+    Ok(user)
+}
+
 fn is_valid_github_login(github_login: &str) -> bool {
     if github_login.len() > 39 {
         return false;
@@ -139,33 +661,209 @@ fn is_valid_github_login(github_login: &str) -> bool {
 
 async fn get_or_create_user_for_trusted_login(
     github_login: &str,
-    db: &Arc<Database>,
+    state: &Arc<AppState>,
 ) -> Result<User> {
+    let db = &state.db;
     if let Some(user) = db.get_user_by_github_login(github_login).await? {
         return Ok(user);
     }
 
-    db.create_user(
-        &format!("{github_login}@example.com"),
-        None,
-        false,
-        NewUserParams {
-            github_login: github_login.to_string(),
-            github_user_id: synthetic_github_user_id(github_login),
-        },
-    )
-    .await?;
+    let (email, params) = match resolve_github_user(state, github_login).await {
+        Some(github_user) => {
+            let email = github_user
+                .email
+                .clone()
+                .unwrap_or_else(|| format!("{}@example.com", github_user.login));
+            (
+                email,
+                NewUserParams {
+                    github_login: github_user.login,
+                    github_user_id: github_user.id,
+                },
+            )
+        }
+        None => (
+            format!("{github_login}@example.com"),
+            NewUserParams {
+                github_login: github_login.to_string(),
+                github_user_id: synthetic_github_user_id(github_login),
+            },
+        ),
+    };
+
+    let user_id = db.create_user(&email, None, false, params).await?;
 
-    db.get_user_by_github_login(github_login)
+    db.get_user_by_id(user_id)
         .await?
-        .with_context(|| format!("user {github_login} not found after create"))
+        .with_context(|| format!("user {user_id} not found after create"))
         .map_err(Into::into)
 }
 
+/// Resolves `github_login` to its authoritative GitHub account via the REST API, when
+/// `state` has a GitHub token configured. Returns `None` (rather than an error) whenever the
+/// API is unavailable, unconfigured, or reports no such login, so callers can fall back to
+/// the synthetic id without failing the request.
+async fn resolve_github_user(state: &Arc<AppState>, github_login: &str) -> Option<GitHubUser> {
+    let github_token = state.config.github_token.as_deref()?;
+    let http_client = state.http_client.as_ref()?;
+
+    match github::get_user(http_client, github_token, github_login).await {
+        Ok(user) => user,
+        Err(error) => {
+            tracing::warn!(
+                "failed to resolve GitHub user {github_login}, falling back to synthetic id: {error:?}"
+            );
+            None
+        }
+    }
+}
+
 fn synthetic_github_user_id(github_login: &str) -> i32 {
     let digest = sha2::Sha256::digest(github_login);
     let mut bytes = [0_u8; 4];
     bytes.copy_from_slice(&digest[..4]);
     let id = i32::from_be_bytes(bytes) & 0x7fff_ffff;
-    if id == 0 { 1 } else { id }
+    if id == 0 {
+        1
+    } else {
+        id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nonce_is_single_use() {
+        let store = NonceStore::new();
+        let user_id = UserId(1);
+        let nonce = store.issue(user_id);
+
+        assert_eq!(store.take(user_id), Some(nonce));
+        assert_eq!(store.take(user_id), None);
+    }
+
+    #[test]
+    fn reissuing_before_expiry_returns_the_same_nonce() {
+        let store = NonceStore::new();
+        let user_id = UserId(1);
+
+        let first = store.issue(user_id);
+        let second = store.issue(user_id);
+
+        assert_eq!(first, second);
+        assert_eq!(store.take(user_id), Some(first));
+    }
+
+    #[test]
+    fn expired_nonce_is_not_returned() {
+        let store = NonceStore::new();
+        let user_id = UserId(1);
+        let nonce = store.issue(user_id);
+
+        let expired_issued_at = Instant::now() - CHALLENGE_NONCE_TTL - Duration::from_secs(1);
+        store
+            .nonces
+            .lock()
+            .unwrap()
+            .insert(user_id, (nonce, expired_issued_at));
+
+        assert_eq!(store.take(user_id), None);
+    }
+
+    #[test]
+    fn reissuing_after_expiry_mints_a_new_nonce() {
+        let store = NonceStore::new();
+        let user_id = UserId(1);
+        let first = store.issue(user_id);
+
+        let expired_issued_at = Instant::now() - CHALLENGE_NONCE_TTL - Duration::from_secs(1);
+        store
+            .nonces
+            .lock()
+            .unwrap()
+            .insert(user_id, (first, expired_issued_at));
+
+        let second = store.issue(user_id);
+        assert_ne!(first, second);
+    }
+
+    fn test_user(id: i32) -> User {
+        User {
+            id: UserId(id),
+            github_login: "user".to_string(),
+            github_user_id: id,
+            email_address: None,
+            admin: false,
+            ed25519_public_key: None,
+        }
+    }
+
+    #[test]
+    fn validation_cache_hit_returns_the_cached_user() {
+        let cache = ValidationCache::new(10, Duration::from_secs(60));
+        let user = test_user(1);
+        cache.insert("salt", UserId(1), "token", user.clone(), None, None);
+
+        let cached = cache.get("salt", UserId(1), "token").unwrap();
+        assert_eq!(cached.user.id, user.id);
+        assert!(!cached.needs_refresh);
+    }
+
+    #[test]
+    fn validation_cache_entry_expires_after_its_ttl() {
+        let cache = ValidationCache::new(10, Duration::from_secs(60));
+        let user = test_user(1);
+        cache.insert("salt", UserId(1), "token", user, None, None);
+
+        let key = ValidationCache::key("salt", UserId(1), "token");
+        cache
+            .entries
+            .lock()
+            .unwrap()
+            .get_mut(&key)
+            .unwrap()
+            .expires_at = Instant::now() - Duration::from_secs(1);
+
+        assert!(cache.get("salt", UserId(1), "token").is_none());
+    }
+
+    #[test]
+    fn validation_cache_invalidate_removes_the_entry() {
+        let cache = ValidationCache::new(10, Duration::from_secs(60));
+        let user = test_user(1);
+        cache.insert("salt", UserId(1), "token", user, None, None);
+
+        cache.invalidate("salt", UserId(1), "token");
+
+        assert!(cache.get("salt", UserId(1), "token").is_none());
+    }
+
+    #[test]
+    fn validation_cache_evicts_least_recently_used_entry_over_capacity() {
+        let cache = ValidationCache::new(1, Duration::from_secs(60));
+        cache.insert("salt", UserId(1), "token-a", test_user(1), None, None);
+        cache.insert("salt", UserId(2), "token-b", test_user(2), None, None);
+
+        assert!(cache.get("salt", UserId(1), "token-a").is_none());
+        assert!(cache.get("salt", UserId(2), "token-b").is_some());
+    }
+
+    #[test]
+    fn validation_cache_flags_entries_nearing_access_token_expiry() {
+        let cache = ValidationCache::new(10, Duration::from_secs(60));
+        cache.insert(
+            "salt",
+            UserId(1),
+            "token",
+            test_user(1),
+            Some("refresh-token".to_string()),
+            Some(1),
+        );
+
+        let cached = cache.get("salt", UserId(1), "token").unwrap();
+        assert!(cached.needs_refresh);
+        assert_eq!(cached.refresh_token.as_deref(), Some("refresh-token"));
+    }
 }
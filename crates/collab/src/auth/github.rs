@@ -0,0 +1,46 @@
+use anyhow::{Context as _, Result};
+use serde::Deserialize;
+
+/// A GitHub account as returned by the GitHub REST API, trimmed to the fields
+/// `get_or_create_user_for_trusted_login` needs to create an accurate `User` record.
+#[derive(Debug, Clone, Deserialize)]
+pub struct GitHubUser {
+    pub id: i32,
+    pub login: String,
+    pub email: Option<String>,
+}
+
+/// Looks up a GitHub user by login via the REST API, authenticating with `github_token`.
+///
+/// Returns `Ok(None)` if GitHub reports the login does not exist (404); any other
+/// non-success status or transport failure is surfaced as an error so callers can fall back
+/// to the synthetic id without mistaking a deleted account for an outage.
+pub async fn get_user(
+    http_client: &reqwest::Client,
+    github_token: &str,
+    login: &str,
+) -> Result<Option<GitHubUser>> {
+    let response = http_client
+        .get(format!("https://api.github.com/users/{login}"))
+        .header("Authorization", format!("Bearer {github_token}"))
+        .header("User-Agent", "zed-collab")
+        .header("Accept", "application/vnd.github+json")
+        .send()
+        .await
+        .context("failed to reach GitHub API")?;
+
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+
+    let response = response
+        .error_for_status()
+        .context("GitHub API returned an error status")?;
+
+    let user: GitHubUser = response
+        .json()
+        .await
+        .context("failed to parse GitHub API response")?;
+
+    Ok(Some(user))
+}
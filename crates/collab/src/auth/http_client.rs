@@ -0,0 +1,38 @@
+use crate::Config;
+use anyhow::{Context as _, Result};
+use std::fs;
+
+/// Builds the `reqwest::Client` used by `AppState` to talk to `zed_cloud`.
+///
+/// When `config.zed_cloud_ca_cert_path` is set, the PEM file at that path is trusted as an
+/// additional root certificate, which lets self-hosted deployments behind an internal PKI
+/// validate TLS against their own CA instead of only the public trust store. When
+/// `config.zed_cloud_client_identity` is set, the PEM file at that path is attached as a
+/// client identity so the connection can perform mutual TLS, whether or not a CA path is
+/// configured. With neither configured, the returned
+/// client behaves exactly like a default `reqwest::Client`.
+pub fn build_zed_cloud_http_client(config: &Config) -> Result<reqwest::Client> {
+    let mut builder = reqwest::Client::builder();
+
+    if let Some(ca_cert_path) = &config.zed_cloud_ca_cert_path {
+        let pem = fs::read(ca_cert_path)
+            .with_context(|| format!("failed to read zed_cloud_ca_cert_path {ca_cert_path:?}"))?;
+        let cert = reqwest::Certificate::from_pem(&pem)
+            .with_context(|| format!("failed to parse PEM certificate at {ca_cert_path:?}"))?;
+        builder = builder.add_root_certificate(cert);
+    }
+
+    if let Some(client_identity_path) = &config.zed_cloud_client_identity {
+        let pem = fs::read(client_identity_path).with_context(|| {
+            format!("failed to read zed_cloud_client_identity {client_identity_path:?}")
+        })?;
+        let identity = reqwest::Identity::from_pem(&pem).with_context(|| {
+            format!("failed to parse client identity PEM at {client_identity_path:?}")
+        })?;
+        builder = builder.identity(identity);
+    }
+
+    builder
+        .build()
+        .context("failed to build zed_cloud HTTP client")
+}
@@ -0,0 +1,51 @@
+use serde::Deserialize;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Config {
+    pub database_url: String,
+    /// Shared secret accepted as `ADMIN_TOKEN:<api_token>` for trusted github-login requests.
+    pub api_token: String,
+    zed_cloud_url: Option<String>,
+    /// When set, `get_or_create_user_for_trusted_login` resolves the authoritative GitHub
+    /// identity for a trusted login via the GitHub API instead of fabricating a synthetic one.
+    pub github_token: Option<String>,
+    /// OAuth2 token endpoint used to exchange a refresh token for a new access token when
+    /// upstream validation of the access token returns 401. Defaults to a path relative to
+    /// `zed_cloud_url` when unset.
+    zed_cloud_token_url: Option<String>,
+    /// Path to a PEM file trusted as an additional root CA when validating TLS connections to
+    /// `zed_cloud_url`, for self-hosted deployments behind an internal PKI.
+    pub zed_cloud_ca_cert_path: Option<String>,
+    /// Path to a PEM file used as a client identity (mutual TLS) when connecting to
+    /// `zed_cloud_url`. Applied whether or not `zed_cloud_ca_cert_path` is set.
+    pub zed_cloud_client_identity: Option<String>,
+    /// Maximum number of resolved token validations to keep in `AppState::validation_cache`.
+    #[serde(default = "default_validation_cache_capacity")]
+    pub validation_cache_capacity: usize,
+    /// How long a cached token validation is trusted before `validate_header` re-validates
+    /// against `zed_cloud`.
+    #[serde(default = "default_validation_cache_ttl_seconds")]
+    pub validation_cache_ttl_seconds: u64,
+}
+
+fn default_validation_cache_capacity() -> usize {
+    10_000
+}
+
+fn default_validation_cache_ttl_seconds() -> u64 {
+    60
+}
+
+impl Config {
+    pub fn zed_cloud_url(&self) -> String {
+        self.zed_cloud_url
+            .clone()
+            .unwrap_or_else(|| "https://cloud.zed.dev".to_string())
+    }
+
+    pub fn zed_cloud_token_url(&self) -> String {
+        self.zed_cloud_token_url
+            .clone()
+            .unwrap_or_else(|| format!("{}/client/token/refresh", self.zed_cloud_url()))
+    }
+}
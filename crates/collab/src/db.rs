@@ -0,0 +1,115 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use sqlx::{PgPool, Row};
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct UserId(pub i32);
+
+impl fmt::Display for UserId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct User {
+    pub id: UserId,
+    pub github_login: String,
+    pub github_user_id: i32,
+    pub email_address: Option<String>,
+    pub admin: bool,
+    /// Base64-encoded Ed25519 public key registered for `zed-key` challenge-response auth,
+    /// if the user has enrolled one.
+    pub ed25519_public_key: Option<String>,
+}
+
+pub struct NewUserParams {
+    pub github_login: String,
+    pub github_user_id: i32,
+}
+
+pub struct Database {
+    pool: PgPool,
+}
+
+impl Database {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn get_user_by_id(&self, id: UserId) -> Result<Option<User>> {
+        let row = sqlx::query(
+            "SELECT id, github_login, github_user_id, email_address, admin, ed25519_public_key \
+             FROM users WHERE id = $1",
+        )
+        .bind(id.0)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.map(Self::user_from_row))
+    }
+
+    /// Looks up a user by GitHub login, case-insensitively: GitHub logins are a case-insensitive
+    /// identifier (the same account can be referenced as `PsM14` or `psm14`), but the casing we
+    /// store is whatever GitHub reports at creation time (or the lowercased login from the
+    /// request when GitHub could not be asked), so an exact match would miss a row whose
+    /// stored casing differs from the caller's.
+    pub async fn get_user_by_github_login(&self, github_login: &str) -> Result<Option<User>> {
+        let row = sqlx::query(
+            "SELECT id, github_login, github_user_id, email_address, admin, ed25519_public_key \
+             FROM users WHERE LOWER(github_login) = LOWER($1)",
+        )
+        .bind(github_login)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.map(Self::user_from_row))
+    }
+
+    pub async fn create_user(
+        &self,
+        email_address: &str,
+        invite_count: Option<i32>,
+        admin: bool,
+        params: NewUserParams,
+    ) -> Result<UserId> {
+        let row = sqlx::query(
+            "INSERT INTO users (email_address, invite_count, admin, github_login, github_user_id) \
+             VALUES ($1, $2, $3, $4, $5) \
+             RETURNING id",
+        )
+        .bind(email_address)
+        .bind(invite_count.unwrap_or(0))
+        .bind(admin)
+        .bind(&params.github_login)
+        .bind(params.github_user_id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(UserId(row.get("id")))
+    }
+
+    /// Registers (or replaces) the Ed25519 public key `user_id` uses for `zed-key`
+    /// challenge-response auth.
+    pub async fn set_ed25519_public_key(&self, user_id: UserId, public_key: &str) -> Result<()> {
+        sqlx::query("UPDATE users SET ed25519_public_key = $1 WHERE id = $2")
+            .bind(public_key)
+            .bind(user_id.0)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    fn user_from_row(row: sqlx::postgres::PgRow) -> User {
+        User {
+            id: UserId(row.get("id")),
+            github_login: row.get("github_login"),
+            github_user_id: row.get("github_user_id"),
+            email_address: row.get("email_address"),
+            admin: row.get("admin"),
+            ed25519_public_key: row.get("ed25519_public_key"),
+        }
+    }
+}
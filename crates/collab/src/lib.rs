@@ -0,0 +1,139 @@
+pub mod auth;
+pub mod config;
+pub mod db;
+pub mod rpc;
+
+pub use config::Config;
+
+use auth::{http_client::build_zed_cloud_http_client, NonceStore, ValidationCache};
+use axum::{
+    extract::{Extension, Query},
+    http::StatusCode,
+    middleware,
+    response::{IntoResponse, Response},
+    routing::{get, put},
+    Json, Router,
+};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use db::{Database, UserId};
+use rpc::Principal;
+use serde::Deserialize;
+use std::{sync::Arc, time::Duration};
+
+pub struct AppState {
+    pub db: Arc<Database>,
+    pub http_client: Option<reqwest::Client>,
+    pub config: Config,
+    pub nonce_store: Arc<NonceStore>,
+    pub validation_cache: Arc<ValidationCache>,
+}
+
+impl AppState {
+    pub fn new(db: Arc<Database>, config: Config) -> anyhow::Result<Arc<Self>> {
+        let http_client = Some(build_zed_cloud_http_client(&config)?);
+        let validation_cache = Arc::new(ValidationCache::new(
+            config.validation_cache_capacity,
+            Duration::from_secs(config.validation_cache_ttl_seconds),
+        ));
+
+        Ok(Arc::new(Self {
+            http_client,
+            nonce_store: Arc::new(NonceStore::new()),
+            validation_cache,
+            db,
+            config,
+        }))
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("{message}")]
+    Http { status: StatusCode, message: String },
+    #[error(transparent)]
+    Internal(#[from] anyhow::Error),
+}
+
+impl Error {
+    pub fn http(status: StatusCode, message: String) -> Self {
+        Self::Http { status, message }
+    }
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+impl IntoResponse for Error {
+    fn into_response(self) -> Response {
+        match self {
+            Error::Http { status, message } => (status, message).into_response(),
+            Error::Internal(error) => {
+                tracing::error!("internal error: {error:?}");
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "internal error".to_string(),
+                )
+                    .into_response()
+            }
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ChallengeQuery {
+    user_id: i32,
+}
+
+/// `GET /authenticate/challenge?user_id=<id>` hands out a `zed-key` challenge nonce for the
+/// given user (the outstanding one while it has not expired). The client signs `nonce || method || path` with its Ed25519 secret key and
+/// replays the request with `Authorization: zed-key <user-id> <base64-signature>`.
+pub async fn issue_challenge(
+    Extension(state): Extension<Arc<AppState>>,
+    Query(query): Query<ChallengeQuery>,
+) -> impl IntoResponse {
+    let nonce = auth::issue_challenge_nonce(&state, UserId(query.user_id));
+    Json(serde_json::json!({ "nonce": BASE64.encode(nonce) }))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SetEd25519PublicKeyBody {
+    /// Base64-encoded Ed25519 public key (32 bytes once decoded).
+    public_key: String,
+}
+
+/// `PUT /authenticate/ed25519_public_key` registers the caller's Ed25519 public key for
+/// subsequent `zed-key` challenge-response logins. Requires an already-authenticated request
+/// (any scheme `validate_header` accepts), since this is how a user bootstraps the key-based
+/// scheme for themselves, not something one user can do on another's behalf.
+pub async fn set_ed25519_public_key(
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(principal): Extension<Principal>,
+    Json(body): Json<SetEd25519PublicKeyBody>,
+) -> Result<impl IntoResponse> {
+    let Principal::User(user) = principal;
+
+    let is_valid_key = BASE64
+        .decode(&body.public_key)
+        .is_ok_and(|bytes| bytes.len() == 32);
+    if !is_valid_key {
+        return Err(Error::http(
+            StatusCode::BAD_REQUEST,
+            "public_key must be a base64-encoded 32-byte Ed25519 public key".to_string(),
+        ));
+    }
+
+    state
+        .db
+        .set_ed25519_public_key(user.id, &body.public_key)
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub fn router() -> Router {
+    Router::new()
+        .route("/authenticate/challenge", get(issue_challenge))
+        .route(
+            "/authenticate/ed25519_public_key",
+            put(set_ed25519_public_key).route_layer(middleware::from_fn(auth::validate_header)),
+        )
+}
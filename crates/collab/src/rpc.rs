@@ -0,0 +1,8 @@
+use crate::db::User;
+
+/// The identity a request has been authenticated as, set on the request's extensions by
+/// `auth::validate_header`.
+#[derive(Debug, Clone)]
+pub enum Principal {
+    User(User),
+}